@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+use rustc_serialize::Decodable;
+
+use errors::{Error, Position};
+use parser::{Event, Number};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Json>),
+    Map(BTreeMap<String, Json>),
+}
+
+impl Json {
+    pub fn into_events(self) -> Vec<Event> {
+        let mut events = Vec::new();
+        push_events(self, &mut events);
+        events
+    }
+}
+
+fn push_events(json: Json, events: &mut Vec<Event>) {
+    match json {
+        Json::Null => events.push(Event::Null),
+        Json::Boolean(b) => events.push(Event::Boolean(b)),
+        Json::Number(n) => events.push(Event::Number(n)),
+        Json::String(s) => events.push(Event::String(s)),
+        Json::Array(items) => {
+            events.push(Event::StartArray);
+            for item in items {
+                push_events(item, events);
+            }
+            events.push(Event::EndArray);
+        }
+        Json::Map(map) => {
+            events.push(Event::StartMap);
+            for (key, value) in map {
+                events.push(Event::Key(key));
+                push_events(value, events);
+            }
+            events.push(Event::EndMap);
+        }
+    }
+}
+
+pub struct Builder<I> {
+    iterator: I,
+}
+
+impl<I: Iterator<Item=Result<Event, Error>>> Builder<I> {
+    pub fn new(iterator: I) -> Builder<I> {
+        Builder { iterator }
+    }
+
+    pub fn build(&mut self) -> Result<Json, Error> {
+        match self.iterator.next() {
+            None => Err(Error::MoreLexemes(Position::start())),
+            Some(Err(e)) => Err(e),
+            Some(Ok(event)) => self.build_from(event),
+        }
+    }
+
+    fn build_from(&mut self, event: Event) -> Result<Json, Error> {
+        match event {
+            Event::Null => Ok(Json::Null),
+            Event::Boolean(b) => Ok(Json::Boolean(b)),
+            Event::Number(n) => Ok(Json::Number(n)),
+            Event::String(s) => Ok(Json::String(s)),
+            Event::StartArray => self.build_array(),
+            Event::StartMap => self.build_map(),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+
+    fn build_array(&mut self) -> Result<Json, Error> {
+        let mut items = Vec::new();
+        loop {
+            match self.iterator.next() {
+                None => return Err(Error::MoreLexemes(Position::start())),
+                Some(Err(e)) => return Err(e),
+                Some(Ok(Event::EndArray)) => return Ok(Json::Array(items)),
+                Some(Ok(event)) => items.push(self.build_from(event)?),
+            }
+        }
+    }
+
+    fn build_map(&mut self) -> Result<Json, Error> {
+        let mut map = BTreeMap::new();
+        loop {
+            match self.iterator.next() {
+                None => return Err(Error::MoreLexemes(Position::start())),
+                Some(Err(e)) => return Err(e),
+                Some(Ok(Event::EndMap)) => return Ok(Json::Map(map)),
+                Some(Ok(Event::Key(k))) => {
+                    let value = self.build()?;
+                    map.insert(k, value);
+                }
+                Some(Ok(other)) => return Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+            }
+        }
+    }
+}
+
+pub fn build_value<I: Iterator<Item=Result<Event, Error>>>(first: Event, iterator: I) -> Result<Json, Error> {
+    let mut builder = Builder::new(iterator);
+    builder.build_from(first)
+}
+
+pub fn decode<T: Decodable>(json: Json) -> Result<T, Error> {
+    let mut decoder = JsonDecoder::new(json);
+    Decodable::decode(&mut decoder)
+}
+
+struct JsonDecoder {
+    stack: Vec<Json>,
+}
+
+impl JsonDecoder {
+    fn new(json: Json) -> JsonDecoder {
+        JsonDecoder { stack: vec![json] }
+    }
+
+    fn pop(&mut self) -> Result<Json, Error> {
+        self.stack.pop().ok_or_else(|| Error::Unexpected("end of value".to_string(), Position::start()))
+    }
+
+    fn push(&mut self, json: Json) {
+        self.stack.push(json)
+    }
+}
+
+impl ::rustc_serialize::Decoder for JsonDecoder {
+    type Error = Error;
+
+    fn read_nil(&mut self) -> Result<(), Error> {
+        match self.pop()? {
+            Json::Null => Ok(()),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+
+    fn read_usize(&mut self) -> Result<usize, Error> { self.read_u64().map(|n| n as usize) }
+    fn read_u64(&mut self) -> Result<u64, Error> {
+        match self.pop()? {
+            Json::Number(Number::U64(n)) => Ok(n),
+            Json::Number(Number::I64(n)) => Ok(n as u64),
+            Json::Number(Number::F64(n)) => Ok(n as u64),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+    fn read_u32(&mut self) -> Result<u32, Error> { self.read_u64().map(|n| n as u32) }
+    fn read_u16(&mut self) -> Result<u16, Error> { self.read_u64().map(|n| n as u16) }
+    fn read_u8(&mut self) -> Result<u8, Error> { self.read_u64().map(|n| n as u8) }
+
+    fn read_isize(&mut self) -> Result<isize, Error> { self.read_i64().map(|n| n as isize) }
+    fn read_i64(&mut self) -> Result<i64, Error> {
+        match self.pop()? {
+            Json::Number(Number::I64(n)) => Ok(n),
+            Json::Number(Number::U64(n)) => Ok(n as i64),
+            Json::Number(Number::F64(n)) => Ok(n as i64),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+    fn read_i32(&mut self) -> Result<i32, Error> { self.read_i64().map(|n| n as i32) }
+    fn read_i16(&mut self) -> Result<i16, Error> { self.read_i64().map(|n| n as i16) }
+    fn read_i8(&mut self) -> Result<i8, Error> { self.read_i64().map(|n| n as i8) }
+
+    fn read_bool(&mut self) -> Result<bool, Error> {
+        match self.pop()? {
+            Json::Boolean(b) => Ok(b),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Error> {
+        match self.pop()? {
+            Json::Number(n) => Ok(n.as_f64()),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+    fn read_f32(&mut self) -> Result<f32, Error> { self.read_f64().map(|n| n as f32) }
+
+    fn read_char(&mut self) -> Result<char, Error> {
+        let s = self.read_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(Error::Unexpected(s, Position::start())),
+        }
+    }
+
+    fn read_str(&mut self) -> Result<String, Error> {
+        match self.pop()? {
+            Json::String(s) => Ok(s),
+            other => Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        }
+    }
+
+    fn read_enum<T, F>(&mut self, _name: &str, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn read_enum_variant<T, F>(&mut self, names: &[&str], mut f: F) -> Result<T, Error>
+        where F: FnMut(&mut Self, usize) -> Result<T, Error> {
+        let name = self.read_str()?;
+        match names.iter().position(|n| *n == name) {
+            Some(idx) => f(self, idx),
+            None => Err(Error::Unexpected(name, Position::start())),
+        }
+    }
+
+    fn read_enum_variant_arg<T, F>(&mut self, _a_idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn read_enum_struct_variant<T, F>(&mut self, names: &[&str], f: F) -> Result<T, Error>
+        where F: FnMut(&mut Self, usize) -> Result<T, Error> {
+        self.read_enum_variant(names, f)
+    }
+
+    fn read_enum_struct_variant_field<T, F>(&mut self, _f_name: &str, f_idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        self.read_enum_variant_arg(f_idx, f)
+    }
+
+    fn read_struct<T, F>(&mut self, _s_name: &str, _len: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn read_struct_field<T, F>(&mut self, f_name: &str, _f_idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        let map = match self.pop()? {
+            Json::Map(map) => map,
+            other => return Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        };
+        let field = map.get(f_name).cloned()
+            .ok_or_else(|| Error::Unexpected(f_name.to_string(), Position::start()))?;
+        self.push(Json::Map(map));
+        self.push(field);
+        f(self)
+    }
+
+    fn read_tuple<T, F>(&mut self, _len: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn read_tuple_arg<T, F>(&mut self, idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        self.read_seq_elt(idx, f)
+    }
+
+    fn read_tuple_struct<T, F>(&mut self, _s_name: &str, len: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        self.read_tuple(len, f)
+    }
+
+    fn read_tuple_struct_arg<T, F>(&mut self, a_idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        self.read_tuple_arg(a_idx, f)
+    }
+
+    fn read_option<T, F>(&mut self, mut f: F) -> Result<T, Error>
+        where F: FnMut(&mut Self, bool) -> Result<T, Error> {
+        match self.pop()? {
+            Json::Null => f(self, false),
+            other => { self.push(other); f(self, true) }
+        }
+    }
+
+    fn read_seq<T, F>(&mut self, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self, usize) -> Result<T, Error> {
+        let items = match self.pop()? {
+            Json::Array(items) => items,
+            other => return Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        };
+        let len = items.len();
+        for item in items.into_iter().rev() {
+            self.push(item);
+        }
+        f(self, len)
+    }
+
+    fn read_seq_elt<T, F>(&mut self, _idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn read_map<T, F>(&mut self, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self, usize) -> Result<T, Error> {
+        let map = match self.pop()? {
+            Json::Map(map) => map,
+            other => return Err(Error::Unexpected(format!("{:?}", other), Position::start())),
+        };
+        let len = map.len();
+        for (key, value) in map.into_iter().rev() {
+            self.push(value);
+            self.push(Json::String(key));
+        }
+        f(self, len)
+    }
+
+    fn read_map_elt_key<T, F>(&mut self, _idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn read_map_elt_val<T, F>(&mut self, _idx: usize, f: F) -> Result<T, Error>
+        where F: FnOnce(&mut Self) -> Result<T, Error> {
+        f(self)
+    }
+
+    fn error(&mut self, err: &str) -> Error {
+        Error::Unexpected(err.to_string(), Position::start())
+    }
+}
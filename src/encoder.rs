@@ -0,0 +1,179 @@
+use std::io::{self, Write};
+
+use errors::{Error, Position};
+use parser::{Event, Number};
+
+enum Frame {
+    Map(bool),
+    Array(bool),
+}
+
+pub struct Encoder<W> {
+    writer: W,
+    indent: Option<String>,
+    stack: Vec<Frame>,
+    lenient: bool,
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn compact(writer: W) -> Encoder<W> {
+        Encoder {
+            writer,
+            indent: None,
+            stack: Vec::new(),
+            lenient: true,
+        }
+    }
+
+    pub fn pretty(writer: W, indent: &str) -> Encoder<W> {
+        Encoder {
+            writer,
+            indent: Some(indent.to_string()),
+            stack: Vec::new(),
+            lenient: true,
+        }
+    }
+
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    pub fn encode<I>(&mut self, events: I) -> Result<(), Error>
+        where I: Iterator<Item=Result<Event, Error>> {
+        for event in events {
+            self.write_event(event?)?;
+        }
+        Ok(())
+    }
+
+    fn newline_indent(&mut self, depth: usize) -> io::Result<()> {
+        if let Some(ref unit) = self.indent {
+            self.writer.write_all(b"\n")?;
+            for _ in 0..depth {
+                self.writer.write_all(unit.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn before_sibling(&mut self) -> io::Result<()> {
+        let mut needs_comma = false;
+        if let Some(frame) = self.stack.last_mut() {
+            let first = match *frame {
+                Frame::Map(ref mut first) => first,
+                Frame::Array(ref mut first) => first,
+            };
+            needs_comma = !*first;
+            *first = false;
+        }
+        if needs_comma {
+            self.writer.write_all(b",")?;
+        }
+        let depth = self.stack.len();
+        self.newline_indent(depth)
+    }
+
+    fn write_event(&mut self, event: Event) -> Result<(), Error> {
+        match event {
+            Event::Key(key) => {
+                self.before_sibling()?;
+                write_string(&mut self.writer, &key)?;
+                self.writer.write_all(if self.indent.is_some() { b": " } else { b":" })?;
+            }
+            Event::EndMap | Event::EndArray => {
+                let closing: &[u8] = if let Event::EndMap = event { b"}" } else { b"]" };
+                let had_children = match self.stack.pop() {
+                    Some(Frame::Map(first)) | Some(Frame::Array(first)) => !first,
+                    None => {
+                        let c = if closing == b"}" { '}' } else { ']' };
+                        return Err(Error::Unmatched(c, Position::start()));
+                    }
+                };
+                if had_children {
+                    let depth = self.stack.len();
+                    self.newline_indent(depth)?;
+                }
+                self.writer.write_all(closing)?;
+            }
+            other => {
+                let is_array_child = matches!(self.stack.last(), Some(&Frame::Array(..)));
+                if is_array_child {
+                    self.before_sibling()?;
+                }
+                self.write_value(other)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_value(&mut self, event: Event) -> Result<(), Error> {
+        match event {
+            Event::Null => self.writer.write_all(b"null")?,
+            Event::Boolean(true) => self.writer.write_all(b"true")?,
+            Event::Boolean(false) => self.writer.write_all(b"false")?,
+            Event::Number(Number::I64(n)) => write!(self.writer, "{}", n)?,
+            Event::Number(Number::U64(n)) => write!(self.writer, "{}", n)?,
+            Event::Number(Number::F64(n)) => write_f64(&mut self.writer, n, self.lenient)?,
+            Event::String(s) => write_string(&mut self.writer, &s)?,
+            Event::StartMap => {
+                self.stack.push(Frame::Map(true));
+                self.writer.write_all(b"{")?;
+            }
+            Event::StartArray => {
+                self.stack.push(Frame::Array(true));
+                self.writer.write_all(b"[")?;
+            }
+            Event::Key(..) | Event::EndMap | Event::EndArray => unreachable!(),
+        }
+        Ok(())
+    }
+}
+
+fn write_f64<W: Write>(writer: &mut W, n: f64, lenient: bool) -> Result<(), Error> {
+    if !n.is_finite() {
+        if !lenient {
+            return Err(Error::NonFiniteNumber(Position::start()));
+        }
+        if n.is_nan() {
+            writer.write_all(b"NaN")?;
+        } else {
+            writer.write_all(if n > 0.0 { b"Infinity" } else { b"-Infinity" })?;
+        }
+        return Ok(());
+    }
+    let s = format!("{}", n);
+    if s.contains('.') || s.contains('e') || s.contains('E') {
+        writer.write_all(s.as_bytes())?;
+    } else {
+        write!(writer, "{}.0", s)?;
+    }
+    Ok(())
+}
+
+fn write_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            '\t' => writer.write_all(b"\\t")?,
+            '\x08' => writer.write_all(b"\\b")?,
+            '\x0c' => writer.write_all(b"\\f")?,
+            c if (c as u32) < 0x20 || (c as u32) > 0x7e => {
+                let code = c as u32;
+                if code > 0xffff {
+                    let code = code - 0x10000;
+                    let high = 0xd800 + (code >> 10);
+                    let low = 0xdc00 + (code & 0x3ff);
+                    write!(writer, "\\u{:04x}\\u{:04x}", high, low)?;
+                } else {
+                    write!(writer, "\\u{:04x}", code)?;
+                }
+            }
+            c => write!(writer, "{}", c)?,
+        }
+    }
+    writer.write_all(b"\"")
+}
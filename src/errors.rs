@@ -19,10 +19,14 @@ pub struct ResultIterator<I: Iterator> {
 impl<I: Iterator> ResultIterator<I> {
     pub fn new(iterator: I) -> ResultIterator<I> {
         ResultIterator {
-            iterator: iterator,
+            iterator,
             errored: false,
         }
     }
+
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.iterator
+    }
 }
 
 impl<T, E, I: Iterator<Item=Result<T, E>>> Iterator for ResultIterator<I> {
@@ -40,50 +44,67 @@ impl<T, E, I: Iterator<Item=Result<T, E>>> Iterator for ResultIterator<I> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl Position {
+    pub fn start() -> Position {
+        Position { line: 1, column: 0, offset: 0 }
+    }
+
+    pub fn advance(&mut self, byte: u8) {
+        self.offset += 1;
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "line {} column {}", self.line, self.column)
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
-    Unterminated,
+    Unterminated(Position),
     IO(io::Error),
-    Unexpected(String),
+    Unexpected(String, Position),
     Utf8(str::Utf8Error),
-    Escape(String),
-    MoreLexemes,
-    Unmatched(char),
-    AdditionalData,
+    Escape(String, Position),
+    MoreLexemes(Position),
+    Unmatched(char, Position),
+    AdditionalData(Position),
+    NonFiniteNumber(Position),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
-            Error::Unterminated => write!(f, "{}", self),
-            Error::IO(_) => write!(f, "I/O Error: {}", self),
-            Error::Unexpected(ref s) => write!(f, "Unexpected lexeme: '{}'", s),
+            Error::Unterminated(ref p) => write!(f, "Unterminated string at {}", p),
+            Error::IO(ref e) => write!(f, "I/O Error: {}", e),
+            Error::Unexpected(ref s, ref p) => write!(f, "Unexpected lexeme: '{}' at {}", s, p),
             Error::Utf8(ref e) => write!(f, "UTF8 Error: {}", e),
-            Error::Escape(ref s) => write!(f, "Malformed escape: '{}'", s),
-            Error::MoreLexemes => write!(f, "More lexemes expected"),
-            Error::Unmatched(ref c) => write!(f, "Unmatched container terminator: {}", c),
-            Error::AdditionalData => write!(f, "Additional data in the source stream after parsed value"),
+            Error::Escape(ref s, ref p) => write!(f, "Malformed escape: '{}' at {}", s, p),
+            Error::MoreLexemes(ref p) => write!(f, "More lexemes expected at {}", p),
+            Error::Unmatched(ref c, ref p) => write!(f, "Unmatched container terminator: {} at {}", c, p),
+            Error::AdditionalData(ref p) => write!(f, "Additional data in the source stream after parsed value at {}", p),
+            Error::NonFiniteNumber(ref p) => write!(f, "NaN/Infinity cannot be encoded in strict mode at {}", p),
         }
     }
 }
 
 impl error::Error for Error {
-    fn description(&self) -> &str {
-        match *self {
-            Error::Unterminated => "unterminated string",
-            Error::IO(ref e) => e.description(),
-            Error::Unexpected(..) => "unexpected lexeme",
-            Error::Utf8(ref e) => e.description(),
-            Error::Escape(..) => "malformed escape",
-            Error::MoreLexemes => "more lexemes expected",
-            Error::Unmatched(..) => "unmatched container terminator",
-            Error::AdditionalData => "additional data",
-        }
-    }
-
-    fn cause(&self) -> Option<&error::Error> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
-            Error::Unterminated => None,
             Error::IO(ref e) => Some(e),
             Error::Utf8(ref e) => Some(e),
             _ => None,
@@ -0,0 +1,186 @@
+use std::io::{self, BufReader, Read};
+use std::str;
+
+use errors::{Error, Position};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lexeme {
+    StartMap,
+    EndMap,
+    StartArray,
+    EndArray,
+    Colon,
+    Comma,
+    String(String),
+    Number(String),
+    Boolean(bool),
+    Null,
+}
+
+pub struct Lexer<R> {
+    bytes: io::Bytes<BufReader<R>>,
+    peeked: Option<u8>,
+    position: Position,
+    token_start: Position,
+    lenient: bool,
+}
+
+impl<R: Read> Lexer<R> {
+    pub fn new(reader: R) -> Lexer<R> {
+        Lexer {
+            bytes: BufReader::new(reader).bytes(),
+            peeked: None,
+            position: Position::start(),
+            token_start: Position::start(),
+            lenient: false,
+        }
+    }
+
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    pub fn token_start(&self) -> Position {
+        self.token_start
+    }
+
+    fn fetch_byte(&mut self) -> io::Result<Option<u8>> {
+        match self.bytes.next() {
+            Some(Ok(b)) => Ok(Some(b)),
+            Some(Err(e)) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        let b = match self.peeked.take() {
+            Some(b) => Some(b),
+            None => self.fetch_byte()?,
+        };
+        if let Some(byte) = b {
+            self.position.advance(byte);
+        }
+        Ok(b)
+    }
+
+    fn peek_byte(&mut self) -> io::Result<Option<u8>> {
+        if self.peeked.is_none() {
+            self.peeked = self.fetch_byte()?;
+        }
+        Ok(self.peeked)
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<()> {
+        loop {
+            match self.peek_byte()? {
+                Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => { self.read_byte()?; }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, literal: &str, lexeme: Lexeme) -> Result<Lexeme, Error> {
+        for expected in literal.bytes() {
+            match self.read_byte()? {
+                Some(b) if b == expected => (),
+                _ => return Err(Error::Unexpected(literal.to_string(), self.token_start)),
+            }
+        }
+        Ok(lexeme)
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        let mut buf = Vec::new();
+        loop {
+            match self.read_byte()? {
+                None => return Err(Error::Unterminated(self.token_start)),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    match self.read_byte()? {
+                        None => return Err(Error::Unterminated(self.token_start)),
+                        Some(b'"') => buf.push(b'"'),
+                        Some(b'\\') => buf.push(b'\\'),
+                        Some(b'/') => buf.push(b'/'),
+                        Some(b'b') => buf.push(0x08),
+                        Some(b'f') => buf.push(0x0c),
+                        Some(b'n') => buf.push(b'\n'),
+                        Some(b'r') => buf.push(b'\r'),
+                        Some(b't') => buf.push(b'\t'),
+                        Some(b'u') => {
+                            let code = self.read_hex4()?;
+                            let c = ::std::char::from_u32(code as u32)
+                                .ok_or_else(|| Error::Escape(format!("\\u{:04x}", code), self.token_start))?;
+                            let mut tmp = [0u8; 4];
+                            buf.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+                        }
+                        Some(b) => return Err(Error::Escape((b as char).to_string(), self.token_start)),
+                    }
+                }
+                Some(b) => buf.push(b),
+            }
+        }
+        Ok(str::from_utf8(&buf)?.to_string())
+    }
+
+    fn read_hex4(&mut self) -> Result<u16, Error> {
+        let mut code = 0u16;
+        for _ in 0..4 {
+            let b = self.read_byte()?.ok_or(Error::Unterminated(self.token_start))?;
+            let digit = (b as char).to_digit(16)
+                .ok_or_else(|| Error::Escape((b as char).to_string(), self.token_start))?;
+            code = code * 16 + digit as u16;
+        }
+        Ok(code)
+    }
+
+    fn read_number_digits(&mut self, mut buf: String) -> Result<String, Error> {
+        while let Some(b @ b'0'..=b'9') | Some(b @ b'.') | Some(b @ b'e') | Some(b @ b'E')
+            | Some(b @ b'+') | Some(b @ b'-') = self.peek_byte()? {
+            buf.push(b as char);
+            self.read_byte()?;
+        }
+        Ok(buf)
+    }
+
+    fn next_lexeme(&mut self) -> Option<Result<Lexeme, Error>> {
+        itry!(self.skip_whitespace());
+        self.token_start = self.position;
+        match itry!(self.peek_byte()) {
+            None => None,
+            Some(b'{') => { itry!(self.read_byte()); Some(Ok(Lexeme::StartMap)) }
+            Some(b'}') => { itry!(self.read_byte()); Some(Ok(Lexeme::EndMap)) }
+            Some(b'[') => { itry!(self.read_byte()); Some(Ok(Lexeme::StartArray)) }
+            Some(b']') => { itry!(self.read_byte()); Some(Ok(Lexeme::EndArray)) }
+            Some(b':') => { itry!(self.read_byte()); Some(Ok(Lexeme::Colon)) }
+            Some(b',') => { itry!(self.read_byte()); Some(Ok(Lexeme::Comma)) }
+            Some(b'"') => {
+                itry!(self.read_byte());
+                Some(self.read_string().map(Lexeme::String))
+            }
+            Some(b't') => Some(self.expect("true", Lexeme::Boolean(true))),
+            Some(b'f') => Some(self.expect("false", Lexeme::Boolean(false))),
+            Some(b'n') => Some(self.expect("null", Lexeme::Null)),
+            Some(b'N') if self.lenient => Some(self.expect("NaN", Lexeme::Number("NaN".to_string()))),
+            Some(b'I') if self.lenient => Some(self.expect("Infinity", Lexeme::Number("Infinity".to_string()))),
+            Some(b'-') => {
+                itry!(self.read_byte());
+                if self.lenient {
+                    if let Some(b'I') = itry!(self.peek_byte()) {
+                        return Some(self.expect("Infinity", Lexeme::Number("-Infinity".to_string())));
+                    }
+                }
+                Some(self.read_number_digits("-".to_string()).map(Lexeme::Number))
+            }
+            Some(b'0'..=b'9') => Some(self.read_number_digits(String::new()).map(Lexeme::Number)),
+            Some(b) => Some(Err(Error::Unexpected((b as char).to_string(), self.token_start))),
+        }
+    }
+}
+
+impl<R: Read> Iterator for Lexer<R> {
+    type Item = Result<Lexeme, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_lexeme()
+    }
+}
@@ -0,0 +1,12 @@
+extern crate rustc_serialize;
+
+#[macro_use]
+pub mod errors;
+pub mod lexer;
+pub mod parser;
+pub mod builder;
+pub mod encoder;
+pub mod pointer;
+
+#[cfg(test)]
+mod test;
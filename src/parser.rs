@@ -0,0 +1,397 @@
+use std::io::{Read, Write};
+
+use encoder::Encoder;
+use errors::{Error, Position, ResultIterator};
+use lexer::{Lexer, Lexeme};
+use pointer::{self, Pointer};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+}
+
+impl Number {
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::I64(n) => n as f64,
+            Number::U64(n) => n as f64,
+            Number::F64(n) => n,
+        }
+    }
+
+    fn parse(raw: &str, position: Position) -> Result<Number, Error> {
+        if !raw.contains('.') && !raw.contains('e') && !raw.contains('E') {
+            if let Ok(n) = raw.parse::<i64>() {
+                return Ok(Number::I64(n));
+            }
+            if !raw.starts_with('-') {
+                if let Ok(n) = raw.parse::<u64>() {
+                    return Ok(Number::U64(n));
+                }
+            }
+        }
+        raw.parse::<f64>().map(Number::F64).map_err(|_| Error::Unexpected(raw.to_string(), position))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    StartMap,
+    EndMap,
+    StartArray,
+    EndArray,
+    Key(String),
+    String(String),
+    Number(Number),
+    Boolean(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Container {
+    Map,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    CommaOrEnd,
+    KeyOrEnd,
+    Colon,
+}
+
+struct Lexemes<R> {
+    lexer: Lexer<R>,
+    stack: Vec<Container>,
+    expect: Expect,
+    done: bool,
+    multi_document: bool,
+}
+
+impl<R: Read> Lexemes<R> {
+    fn new(reader: R) -> Lexemes<R> {
+        Lexemes {
+            lexer: Lexer::new(reader),
+            stack: Vec::new(),
+            expect: Expect::Value,
+            done: false,
+            multi_document: false,
+        }
+    }
+
+    fn set_lenient(&mut self, lenient: bool) {
+        self.lexer.set_lenient(lenient);
+    }
+
+    fn emit(&mut self, event: Event) -> Option<Result<Event, Error>> {
+        Some(Ok(event))
+    }
+
+    fn start_value(&mut self, lexeme: Lexeme, position: Position) -> Result<Event, Error> {
+        let event = match lexeme {
+            Lexeme::StartMap => {
+                self.stack.push(Container::Map);
+                self.expect = Expect::KeyOrEnd;
+                Event::StartMap
+            }
+            Lexeme::StartArray => {
+                self.stack.push(Container::Array);
+                self.expect = Expect::Value;
+                Event::StartArray
+            }
+            Lexeme::EndArray if self.stack.last() == Some(&Container::Array) => {
+                self.stack.pop();
+                self.expect = Expect::CommaOrEnd;
+                Event::EndArray
+            }
+            Lexeme::String(s) => { self.expect = Expect::CommaOrEnd; Event::String(s) }
+            Lexeme::Number(n) => {
+                self.expect = Expect::CommaOrEnd;
+                Event::Number(Number::parse(&n, position)?)
+            }
+            Lexeme::Boolean(b) => { self.expect = Expect::CommaOrEnd; Event::Boolean(b) }
+            Lexeme::Null => { self.expect = Expect::CommaOrEnd; Event::Null }
+            other => return Err(Error::Unexpected(format!("{:?}", other), position)),
+        };
+        if self.stack.is_empty() {
+            self.expect = Expect::CommaOrEnd;
+        }
+        Ok(event)
+    }
+}
+
+impl<R: Read> Iterator for Lexemes<R> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            if self.expect == Expect::Colon {
+                match itry!(self.lexer.next().unwrap_or(Err(Error::MoreLexemes(self.lexer.token_start())))) {
+                    Lexeme::Colon => { self.expect = Expect::Value; continue; }
+                    other => return Some(Err(Error::Unexpected(format!("{:?}", other), self.lexer.token_start()))),
+                }
+            }
+
+            if self.expect == Expect::CommaOrEnd || self.expect == Expect::KeyOrEnd {
+                let top = match self.stack.last() {
+                    Some(t) => *t,
+                    None => {
+                        if !self.multi_document {
+                            self.done = true;
+                            return match self.lexer.next() {
+                                None => None,
+                                Some(Err(e)) => Some(Err(e)),
+                                Some(Ok(_)) => Some(Err(Error::AdditionalData(self.lexer.token_start()))),
+                            };
+                        }
+                        return match self.lexer.next() {
+                            None => { self.done = true; None }
+                            Some(Err(e)) => Some(Err(e)),
+                            Some(Ok(lexeme)) => {
+                                let position = self.lexer.token_start();
+                                Some(self.start_value(lexeme, position))
+                            }
+                        };
+                    }
+                };
+                let lexeme = match self.lexer.next() {
+                    None => return Some(Err(Error::MoreLexemes(self.lexer.token_start()))),
+                    Some(Err(e)) => return Some(Err(e)),
+                    Some(Ok(l)) => l,
+                };
+                match (top, lexeme) {
+                    (Container::Map, Lexeme::EndMap) => {
+                        self.stack.pop();
+                        self.expect = Expect::CommaOrEnd;
+                        return self.emit(Event::EndMap);
+                    }
+                    (Container::Array, Lexeme::EndArray) => {
+                        self.stack.pop();
+                        self.expect = Expect::CommaOrEnd;
+                        return self.emit(Event::EndArray);
+                    }
+                    (Container::Map, Lexeme::EndArray) => {
+                        return Some(Err(Error::Unmatched(']', self.lexer.token_start())));
+                    }
+                    (Container::Array, Lexeme::EndMap) => {
+                        return Some(Err(Error::Unmatched('}', self.lexer.token_start())));
+                    }
+                    (_, Lexeme::Comma) if self.expect == Expect::CommaOrEnd => {
+                        self.expect = if top == Container::Map { Expect::KeyOrEnd } else { Expect::Value };
+                        continue;
+                    }
+                    (Container::Map, Lexeme::String(s)) if self.expect == Expect::KeyOrEnd => {
+                        self.expect = Expect::Colon;
+                        return self.emit(Event::Key(s));
+                    }
+                    (_, other) => return Some(Err(Error::Unexpected(format!("{:?}", other), self.lexer.token_start()))),
+                }
+            }
+
+            // Expect::Value
+            let lexeme = match self.lexer.next() {
+                None => return Some(Err(Error::MoreLexemes(self.lexer.token_start()))),
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(l)) => l,
+            };
+            let position = self.lexer.token_start();
+            return match self.start_value(lexeme, position) {
+                Ok(event) => self.emit(event),
+                Err(e) => Some(Err(e)),
+            };
+        }
+    }
+}
+
+pub struct Parser<R: Read> {
+    iterator: ResultIterator<Lexemes<R>>,
+}
+
+impl<R: Read> Parser<R> {
+    pub fn new(reader: R) -> Parser<R> {
+        Parser {
+            iterator: ResultIterator::new(Lexemes::new(reader)),
+        }
+    }
+
+    pub fn prefix(self, path: &str) -> Prefix<Parser<R>> {
+        Matcher::new(self, path.to_string())
+    }
+
+    pub fn items(self, path: &str) -> Items<Parser<R>> {
+        Items::new(self, path)
+    }
+
+    pub fn pointer(self, path: &str) -> Result<Pointer<Parser<R>>, Error> {
+        pointer::new(self, path)
+    }
+
+    pub fn lenient(mut self) -> Parser<R> {
+        self.iterator.get_mut().set_lenient(true);
+        self
+    }
+
+    pub fn multi_document(mut self) -> Parser<R> {
+        self.iterator.get_mut().multi_document = true;
+        self
+    }
+
+    pub fn compact<W: Write>(self, writer: W) -> Result<(), Error> {
+        Encoder::compact(writer).encode(self)
+    }
+
+    pub fn pretty<W: Write>(self, writer: W, indent: &str) -> Result<(), Error> {
+        Encoder::pretty(writer, indent).encode(self)
+    }
+}
+
+impl<R: Read> Iterator for Parser<R> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iterator.next()
+    }
+}
+
+/// Tracks "where am I" as events flow past, so a `Matcher` can recognize when the
+/// current position reaches its target path. `PathTracker` (dotted prefixes) and
+/// `pointer::PointerTracker` (RFC 6901) are the two implementations.
+pub trait PathObserver {
+    type Path: PartialEq;
+
+    fn new() -> Self;
+    fn current(&self) -> Self::Path;
+    fn observe(&mut self, event: &Event);
+}
+
+pub struct PathTracker {
+    labels: Vec<String>,
+}
+
+impl PathObserver for PathTracker {
+    type Path = String;
+
+    fn new() -> PathTracker {
+        PathTracker { labels: Vec::new() }
+    }
+
+    fn current(&self) -> String {
+        self.labels.join(".")
+    }
+
+    fn observe(&mut self, event: &Event) {
+        match *event {
+            Event::Key(ref k) => {
+                if let Some(last) = self.labels.last_mut() {
+                    *last = k.clone();
+                }
+            }
+            Event::StartMap | Event::StartArray => {
+                self.labels.push("item".to_string());
+            }
+            Event::EndMap | Event::EndArray => {
+                self.labels.pop();
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Emits only the subtrees at `target` (every one of them, when the path occurs
+/// more than once), walking a `PathObserver` alongside the event stream to
+/// recognize when the current position reaches it.
+pub struct Matcher<I, O: PathObserver> {
+    iterator: I,
+    target: O::Path,
+    tracker: O,
+    depth: usize,
+    matching: bool,
+}
+
+impl<I: Iterator<Item=Result<Event, Error>>, O: PathObserver> Matcher<I, O> {
+    pub fn new(iterator: I, target: O::Path) -> Matcher<I, O> {
+        Matcher {
+            iterator,
+            target,
+            tracker: O::new(),
+            depth: 0,
+            matching: false,
+        }
+    }
+}
+
+impl<I: Iterator<Item=Result<Event, Error>>, O: PathObserver> Iterator for Matcher<I, O> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let event = itry!(self.iterator.next()?);
+
+            if self.matching {
+                match event {
+                    Event::StartMap | Event::StartArray => self.depth += 1,
+                    Event::EndMap | Event::EndArray => self.depth -= 1,
+                    _ => (),
+                }
+                let done = self.depth == 0;
+                self.tracker.observe(&event);
+                if done {
+                    self.matching = false;
+                }
+                return Some(Ok(event));
+            }
+
+            let before = self.tracker.current();
+            let is_value_start = match event {
+                Event::Null | Event::Boolean(..) | Event::Number(..) | Event::String(..)
+                | Event::StartMap | Event::StartArray => true,
+                Event::Key(..) | Event::EndMap | Event::EndArray => false,
+            };
+            self.tracker.observe(&event);
+
+            if is_value_start && before == self.target {
+                self.matching = true;
+                self.depth = match event {
+                    Event::StartMap | Event::StartArray => 1,
+                    _ => 0,
+                };
+                if self.depth == 0 {
+                    self.matching = false;
+                }
+                return Some(Ok(event));
+            }
+        }
+    }
+}
+
+pub type Prefix<I> = Matcher<I, PathTracker>;
+
+pub struct Items<I> {
+    prefix: Prefix<I>,
+}
+
+impl<I: Iterator<Item=Result<Event, Error>>> Items<I> {
+    fn new(iterator: I, path: &str) -> Items<I> {
+        Items { prefix: Matcher::new(iterator, path.to_string()) }
+    }
+}
+
+impl<I: Iterator<Item=Result<Event, Error>>> Iterator for Items<I> {
+    type Item = Result<::builder::Json, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.prefix.next() {
+            None => return None,
+            Some(Err(e)) => return Some(Err(e)),
+            Some(Ok(e)) => e,
+        };
+        Some(::builder::build_value(first, &mut self.prefix))
+    }
+}
@@ -0,0 +1,83 @@
+use builder;
+use errors::{Error, Position};
+use parser::{Event, Matcher, PathObserver};
+
+fn parse(pointer: &str) -> Result<Vec<String>, Error> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(Error::Unexpected(pointer.to_string(), Position::start()));
+    }
+    Ok(pointer[1..].split('/').map(|raw| raw.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+enum Frame {
+    Map { current_key: Option<String> },
+    Array { next_index: usize },
+}
+
+pub struct PointerTracker {
+    frames: Vec<Frame>,
+}
+
+impl PointerTracker {
+    fn bump_parent(&mut self) {
+        if let Some(&mut Frame::Array { ref mut next_index }) = self.frames.last_mut() {
+            *next_index += 1;
+        }
+    }
+}
+
+impl PathObserver for PointerTracker {
+    type Path = Vec<String>;
+
+    fn new() -> PointerTracker {
+        PointerTracker { frames: Vec::new() }
+    }
+
+    fn current(&self) -> Vec<String> {
+        self.frames.iter().map(|frame| {
+            match *frame {
+                Frame::Map { ref current_key } => current_key.clone().unwrap_or_default(),
+                Frame::Array { next_index } => next_index.to_string(),
+            }
+        }).collect()
+    }
+
+    fn observe(&mut self, event: &Event) {
+        match *event {
+            Event::Key(ref k) => {
+                if let Some(&mut Frame::Map { ref mut current_key }) = self.frames.last_mut() {
+                    *current_key = Some(k.clone());
+                }
+            }
+            Event::StartMap => self.frames.push(Frame::Map { current_key: None }),
+            Event::StartArray => self.frames.push(Frame::Array { next_index: 0 }),
+            Event::EndMap | Event::EndArray => {
+                self.frames.pop();
+                self.bump_parent();
+            }
+            Event::Null | Event::Boolean(..) | Event::Number(..) | Event::String(..) => {
+                self.bump_parent();
+            }
+        }
+    }
+}
+
+pub type Pointer<I> = Matcher<I, PointerTracker>;
+
+pub fn new<I>(iterator: I, pointer: &str) -> Result<Pointer<I>, Error>
+    where I: Iterator<Item=Result<Event, Error>> {
+    Ok(Matcher::new(iterator, parse(pointer)?))
+}
+
+pub fn select<I>(events: I, pointer: &str) -> Result<Option<builder::Json>, Error>
+    where I: Iterator<Item=Result<Event, Error>> {
+    let mut matched = new(events, pointer)?;
+    match matched.next() {
+        None => Ok(None),
+        Some(Err(e)) => Err(e),
+        Some(Ok(first)) => builder::build_value(first, matched).map(Some),
+    }
+}
@@ -2,9 +2,13 @@ use std::fs::File;
 use std::io::Cursor;
 use std::result::Result;
 
+use rustc_serialize::{Decodable, Decoder};
+
 use ::errors::Error;
-use ::parser::{Parser, Event};
-use ::builder::{Builder, decode};
+use ::parser::{Parser, Event, Number};
+use ::builder::{Json, decode};
+use ::encoder::Encoder;
+use ::pointer;
 
 
 fn reference_events() -> Vec<Event> {
@@ -20,13 +24,13 @@ fn reference_events() -> Vec<Event> {
                 Event::Key("true".to_string()),
                 Event::Boolean(true),
                 Event::Key("integer".to_string()),
-                Event::Number(0f64),
+                Event::Number(Number::I64(0)),
                 Event::Key("double".to_string()),
-                Event::Number(0.5f64),
+                Event::Number(Number::F64(0.5)),
                 Event::Key("exponent".to_string()),
-                Event::Number(100f64),
+                Event::Number(Number::F64(100f64)),
                 Event::Key("long".to_string()),
-                Event::Number(10000000000f64),
+                Event::Number(Number::I64(10000000000)),
                 Event::Key("string".to_string()),
                 Event::String("строка - тест".to_string()),
             Event::EndMap,
@@ -34,7 +38,7 @@ fn reference_events() -> Vec<Event> {
                 Event::Key("meta".to_string()),
                 Event::StartArray,
                     Event::StartArray,
-                        Event::Number(1f64),
+                        Event::Number(Number::I64(1)),
                     Event::EndArray,
                     Event::StartMap,
                     Event::EndMap,
@@ -76,25 +80,73 @@ fn prefixes() {
     let result: Vec<_> = Parser::new(f).prefix("docs.item.meta.item").map(Result::unwrap).collect();
     assert_eq!(result, vec![
         Event::StartArray,
-        Event::Number(1f64),
+        Event::Number(Number::I64(1)),
         Event::EndArray,
         Event::StartMap,
         Event::EndMap,
     ]);
 }
 
+#[test]
+fn pointers() {
+    let f = File::open("test.json").unwrap();
+    let full: Vec<_> = Parser::new(f).map(Result::unwrap).collect();
+    let f = File::open("test.json").unwrap();
+    let result: Vec<_> = Parser::new(f).pointer("").unwrap().map(Result::unwrap).collect();
+    assert_eq!(result, full);
+
+    let f = File::open("test.json").unwrap();
+    let result: Vec<_> = Parser::new(f).pointer("/docs/1/meta/0").unwrap().map(Result::unwrap).collect();
+    assert_eq!(result, vec![
+        Event::StartArray,
+        Event::Number(Number::I64(1)),
+        Event::EndArray,
+    ]);
+
+    let f = File::open("test.json").unwrap();
+    let result: Vec<_> = Parser::new(f).pointer("/docs/1/meta/1").unwrap().map(Result::unwrap).collect();
+    assert_eq!(result, vec![Event::StartMap, Event::EndMap]);
+
+    let f = File::open("test.json").unwrap();
+    let result = pointer::select(Parser::new(f), "/docs/2/meta/key").unwrap();
+    assert_eq!(result, Some(Json::String("value".to_string())));
+
+    let f = File::open("test.json").unwrap();
+    let result = pointer::select(Parser::new(f), "/docs/3/meta/missing").unwrap();
+    assert_eq!(result, None);
+
+    let s = Cursor::new(br#"{"0": "zero"}"#.to_vec());
+    let result = pointer::select(Parser::new(s), "/0").unwrap();
+    assert_eq!(result, Some(Json::String("zero".to_string())));
+}
+
 #[test]
 fn items() {
     let f = File::open("test.json").unwrap();
     let result: Vec<_> = Parser::new(f).items("").map(Result::unwrap).collect();
     assert_eq!(result.len(), 1);
 
-    #[derive(RustcDecodable, Debug, PartialEq)]
+    #[derive(Debug, PartialEq)]
     struct Person {
         name: String,
         friends: Vec<String>,
     }
 
+    impl Decodable for Person {
+        fn decode<D: Decoder>(d: &mut D) -> Result<Person, D::Error> {
+            d.read_struct("Person", 2, |d| {
+                Ok(Person {
+                    name: d.read_struct_field("name", 0, |d| d.read_str())?,
+                    friends: d.read_struct_field("friends", 1, |d| {
+                        d.read_seq(|d, len| {
+                            (0..len).map(|i| d.read_seq_elt(i, |d| d.read_str())).collect()
+                        })
+                    })?,
+                })
+            })
+        }
+    }
+
     let f = File::open("people.json").unwrap();
     let json = Parser::new(f).items("item").next().unwrap().unwrap();
     let result: Person = decode(json).unwrap();
@@ -105,13 +157,130 @@ fn items() {
     assert_eq!(result, reference);
 }
 
+#[test]
+fn decode_large_u64() {
+    let json = Json::Number(Number::U64(18446744073709551614));
+    let result: u64 = decode(json).unwrap();
+    assert_eq!(result, 18446744073709551614);
+}
+
 #[test]
 fn unterminated_string() {
     let s = Cursor::new(br#"{"key": "value"#.to_vec());
     let r = Parser::new(s).last().unwrap();
     assert!(r.is_err());
     match r.err().unwrap() {
-        Error::Unterminated => (),
-        _ => panic!("Not {}", Error::Unterminated),
+        Error::Unterminated(..) => (),
+        other => panic!("Not Unterminated: {:?}", other),
+    }
+}
+
+#[test]
+fn compact_round_trip() {
+    let f = File::open("test.json").unwrap();
+    let mut out = Vec::new();
+    Parser::new(f).compact(&mut out).unwrap();
+
+    let reparsed: Vec<_> = Parser::new(Cursor::new(out)).map(Result::unwrap).collect();
+    assert_eq!(reparsed, reference_events());
+}
+
+#[test]
+fn whole_number_float_round_trip() {
+    let events = vec![Event::Number(Number::F64(100f64))];
+    let mut out = Vec::new();
+    Encoder::compact(&mut out).encode(events.into_iter().map(Ok)).unwrap();
+    assert_eq!(String::from_utf8(out.clone()).unwrap(), "100.0");
+
+    let reparsed: Vec<_> = Parser::new(Cursor::new(out)).map(Result::unwrap).collect();
+    assert_eq!(reparsed, vec![Event::Number(Number::F64(100f64))]);
+}
+
+#[test]
+fn pretty() {
+    let s = Cursor::new(br#"{"a": [1, 2], "b": {}}"#.to_vec());
+    let mut out = Vec::new();
+    Parser::new(s).pretty(&mut out, "  ").unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}");
+}
+
+#[test]
+fn lenient_numbers() {
+    let s = Cursor::new(br#"{"a": NaN, "b": Infinity, "c": -Infinity}"#.to_vec());
+    let r = Parser::new(s).last().unwrap();
+    assert!(r.is_err());
+
+    let s = Cursor::new(br#"{"a": NaN, "b": Infinity, "c": -Infinity}"#.to_vec());
+    let events: Vec<_> = Parser::new(s).lenient().map(Result::unwrap).collect();
+    let numbers: Vec<_> = events.into_iter().filter_map(|event| {
+        match event {
+            Event::Number(Number::F64(n)) => Some(n),
+            _ => None,
+        }
+    }).collect();
+    assert_eq!(numbers.len(), 3);
+    assert!(numbers[0].is_nan());
+    assert_eq!(numbers[1], f64::INFINITY);
+    assert_eq!(numbers[2], f64::NEG_INFINITY);
+}
+
+#[test]
+fn encode_non_finite() {
+    let s = Cursor::new(br#"[NaN, Infinity, -Infinity]"#.to_vec());
+    let mut out = Vec::new();
+    Parser::new(s).lenient().compact(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out.clone()).unwrap(), "[NaN,Infinity,-Infinity]");
+
+    let reparsed: Vec<_> = Parser::new(Cursor::new(out)).lenient().map(Result::unwrap).collect();
+    let numbers: Vec<_> = reparsed.into_iter().filter_map(|event| {
+        match event {
+            Event::Number(Number::F64(n)) => Some(n),
+            _ => None,
+        }
+    }).collect();
+    assert_eq!(numbers.len(), 3);
+    assert!(numbers[0].is_nan());
+    assert_eq!(numbers[1], f64::INFINITY);
+    assert_eq!(numbers[2], f64::NEG_INFINITY);
+}
+
+#[test]
+fn encode_non_finite_strict() {
+    let events = vec![Event::Number(Number::F64(f64::NAN))];
+    let mut out = Vec::new();
+    let mut encoder = Encoder::compact(&mut out);
+    encoder.set_lenient(false);
+    let r = encoder.encode(events.into_iter().map(Ok));
+    match r {
+        Err(Error::NonFiniteNumber(..)) => (),
+        other => panic!("Not NonFiniteNumber: {:?}", other),
+    }
+}
+
+#[test]
+fn multi_document() {
+    let s = Cursor::new(b"1 2 3".to_vec());
+    let r = Parser::new(s).last().unwrap();
+    assert!(r.is_err());
+
+    let s = Cursor::new(b"1 2\n3".to_vec());
+    let events: Vec<_> = Parser::new(s).multi_document().map(Result::unwrap).collect();
+    assert_eq!(events, vec![
+        Event::Number(Number::I64(1)),
+        Event::Number(Number::I64(2)),
+        Event::Number(Number::I64(3)),
+    ]);
+}
+
+#[test]
+fn error_position() {
+    let s = Cursor::new(b"{\n  \"key\": tru\n}".to_vec());
+    let r = Parser::new(s).last().unwrap();
+    match r.err().unwrap() {
+        Error::Unexpected(_, position) => {
+            assert_eq!(position.line, 2);
+            assert_eq!(position.column, 9);
+        }
+        other => panic!("Not Unexpected: {:?}", other),
     }
 }